@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{Error, Network, WaitStrategy};
+
+static CONTAINER_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Generates a name unique to this process, so containers are reachable by
+/// name on a docker network without callers having to pick one themselves.
+fn generate_container_name(image_name: &str) -> String {
+    let sanitized = image_name.replace(['/', ':'], "-");
+    let counter = CONTAINER_COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("docker-db-{sanitized}-{}-{counter}", std::process::id())
+}
+
+/// Configuration for a docker image, built up before it is spawned into a
+/// running [`Container`].
+///
+/// This is the generic building block behind the [`crate::Postgres`],
+/// [`crate::MySQL`] and [`crate::Redis`] presets - reach for it directly
+/// when you need a container this crate doesn't already wrap.
+pub struct GenericImage {
+    name: String,
+    tag: String,
+    container_name: String,
+    exposed_ports: Vec<u16>,
+    env_vars: Vec<(String, String)>,
+    wait_strategy: WaitStrategy,
+    pull: bool,
+    network: Option<String>,
+}
+
+impl GenericImage {
+    /// Creates a new image configuration for `<name>:<tag>`, e.g.
+    /// `GenericImage::new("redis", "7")`. By default nothing is waited on -
+    /// use [`GenericImage::with_wait_strategy`] to wait for readiness.
+    pub fn new(name: impl Into<String>, tag: impl Into<String>) -> Self {
+        let name = name.into();
+        let container_name = generate_container_name(&name);
+
+        GenericImage {
+            name,
+            tag: tag.into(),
+            container_name,
+            exposed_ports: Vec::new(),
+            env_vars: Vec::new(),
+            wait_strategy: WaitStrategy::None,
+            pull: false,
+            network: None,
+        }
+    }
+
+    /// Overrides the image tag set in [`GenericImage::new`], e.g.
+    /// `.with_tag("16-alpine")`.
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = tag.into();
+        self
+    }
+
+    /// Exposes a port from the container, mapped to a random port on the host.
+    pub fn with_exposed_port(mut self, port: u16) -> Self {
+        self.exposed_ports.push(port);
+        self
+    }
+
+    /// Sets an environment variable inside the container.
+    pub fn with_env_var(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env_vars.push((key.into(), value.into()));
+        self
+    }
+
+    /// Sets the strategy used to decide when the container is ready, run
+    /// once after docker reports it started. Defaults to
+    /// [`WaitStrategy::None`].
+    pub fn with_wait_strategy(mut self, wait_strategy: WaitStrategy) -> Self {
+        self.wait_strategy = wait_strategy;
+        self
+    }
+
+    /// When `true`, runs `docker pull` for the image before starting it,
+    /// rather than relying on whatever is already cached locally.
+    pub fn with_pull(mut self, pull: bool) -> Self {
+        self.pull = pull;
+        self
+    }
+
+    /// Joins `network` so the container is reachable by name from other
+    /// containers on the same network.
+    pub fn with_network(mut self, network: &Network) -> Self {
+        self.network = Some(network.name().to_string());
+        self
+    }
+
+    /// Starts the container in the background and waits for docker to
+    /// report its mapped ports. The container will be stopped and removed
+    /// when the returned [`Container`] is dropped.
+    ///
+    /// Note that we're using sync code here so we'll block the executor - but only for a short moment
+    /// as the container will run in the background.
+    pub async fn spawn(self) -> Result<Container, Error> {
+        let image = format!("{}:{}", self.name, self.tag);
+
+        if self.pull {
+            run_cmd("docker", &["pull".to_string(), image.clone()])?;
+        }
+
+        let mut args = vec!["run".to_string(), "--rm".to_string(), "-d".to_string()];
+
+        args.push("--name".to_string());
+        args.push(self.container_name.clone());
+
+        if let Some(network) = &self.network {
+            args.push("--network".to_string());
+            args.push(network.clone());
+        }
+
+        for (key, value) in &self.env_vars {
+            args.push("-e".to_string());
+            args.push(format!("{key}={value}"));
+        }
+
+        for port in &self.exposed_ports {
+            args.push("-p".to_string());
+            args.push(port.to_string());
+        }
+
+        args.push(image);
+
+        let container_id = run_cmd_to_output("docker", &args)?;
+
+        let mut ports = HashMap::new();
+        for port in &self.exposed_ports {
+            let exposed_port = run_cmd_to_output(
+                "docker",
+                &[
+                    "container".to_string(),
+                    "port".to_string(),
+                    container_id.clone(),
+                    port.to_string(),
+                ],
+            )?;
+            ports.insert(*port, parse_exposed_port(&exposed_port)?);
+        }
+
+        self.wait_strategy.wait(&container_id, &ports)?;
+
+        Ok(Container {
+            container_id,
+            container_name: self.container_name,
+            ports,
+        })
+    }
+}
+
+/// A running docker container, spawned from a [`GenericImage`].
+///
+/// Dropping this value stops and removes the container.
+pub struct Container {
+    container_id: String,
+    container_name: String,
+    ports: HashMap<u16, SocketAddr>,
+}
+
+impl Container {
+    /// Shorthand for [`GenericImage::new`], so `Container::new("redis",
+    /// "7").with_exposed_port(6379).spawn()` works without spelling out
+    /// `GenericImage` first.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(name: impl Into<String>, tag: impl Into<String>) -> GenericImage {
+        GenericImage::new(name, tag)
+    }
+
+    /// The docker-assigned id of the running container.
+    pub fn id(&self) -> &str {
+        &self.container_id
+    }
+
+    /// The name this container was started with, e.g. via `--name`. This is
+    /// what it's reachable as from other containers on the same
+    /// [`Network`](crate::Network).
+    pub fn name(&self) -> &str {
+        &self.container_name
+    }
+
+    /// The host socket address that `container_port` is mapped to, if it
+    /// was exposed via [`GenericImage::with_exposed_port`].
+    pub fn socket_addr(&self, container_port: u16) -> Option<SocketAddr> {
+        self.ports.get(&container_port).copied()
+    }
+
+    /// Same as [`Container::socket_addr`], formatted as a string.
+    pub fn address(&self, container_port: u16) -> Option<String> {
+        self.socket_addr(container_port).map(|addr| addr.to_string())
+    }
+
+    /// Runs `docker exec <id> <args...>` and checks that it exits
+    /// successfully, discarding its output.
+    pub fn exec(&self, args: &[&str]) -> Result<(), Error> {
+        run_cmd_status_ok("docker", &self.exec_args(args))
+    }
+
+    /// Runs `docker exec <id> <args...>` and returns its captured stdout,
+    /// failing with [`Error::CommandFailed`] if it exits non-zero.
+    pub fn output(&self, args: &[&str]) -> Result<String, Error> {
+        run_cmd_to_output_checked("docker", &self.exec_args(args))
+    }
+
+    /// Writes `contents` into the container at `container_path`, via a
+    /// temporary file and `docker cp`.
+    pub fn cp(&self, container_path: &str, contents: &[u8]) -> Result<(), Error> {
+        let tmp_path = std::env::temp_dir().join(format!(
+            "docker-db-cp-{}-{}",
+            self.container_id,
+            container_path.replace('/', "_")
+        ));
+
+        std::fs::write(&tmp_path, contents)?;
+
+        let dest = format!("{}:{}", self.container_id, container_path);
+        let result = run_cmd_status_ok(
+            "docker",
+            &["cp".to_string(), tmp_path.display().to_string(), dest],
+        );
+
+        let _ = std::fs::remove_file(&tmp_path);
+
+        result
+    }
+
+    fn exec_args(&self, args: &[&str]) -> Vec<String> {
+        let mut full_args = vec!["exec".to_string(), self.container_id.clone()];
+        full_args.extend(args.iter().map(|arg| arg.to_string()));
+        full_args
+    }
+}
+
+impl Drop for Container {
+    fn drop(&mut self) {
+        if let Err(err) = run_cmd("docker", &["stop".to_string(), self.container_id.clone()]) {
+            eprintln!("Failed to stop docker container: {}", err);
+        }
+
+        // Redundant, but better safe than sorry
+        if let Err(err) = run_cmd("docker", &["rm".to_string(), self.container_id.clone()]) {
+            eprintln!("Failed to remove docker container: {}", err);
+        }
+    }
+}
+
+pub(crate) fn run_cmd_to_output(program: &str, args: &[String]) -> Result<String, Error> {
+    let mut command = Command::new(program);
+
+    for arg in args {
+        command.arg(arg);
+    }
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::null());
+
+    let Ok(output) = command.output() else {
+        return Ok(String::new());
+    };
+
+    let utf = String::from_utf8(output.stdout).map_err(|err| {
+        eprintln!("Failed to parse command output: {}", err);
+        Error::InvalidOutput
+    })?;
+
+    Ok(utf.trim().to_string())
+}
+
+pub(crate) fn run_cmd(program: &str, args: &[String]) -> Result<(), Error> {
+    run_cmd_to_output(program, args)?;
+
+    Ok(())
+}
+
+/// Like [`run_cmd`], but fails with [`Error::CommandFailed`] if the
+/// process exits with a non-zero status, rather than silently ignoring it.
+pub(crate) fn run_cmd_status_ok(program: &str, args: &[String]) -> Result<(), Error> {
+    let command_line = || format!("{program} {}", args.join(" "));
+
+    let status = Command::new(program)
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|_| Error::CommandFailed(command_line()))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::CommandFailed(command_line()))
+    }
+}
+
+/// Like [`run_cmd_to_output`], but fails with [`Error::CommandFailed`] if
+/// the process exits with a non-zero status, rather than silently
+/// returning whatever (possibly empty) stdout it produced.
+pub(crate) fn run_cmd_to_output_checked(program: &str, args: &[String]) -> Result<String, Error> {
+    let command_line = || format!("{program} {}", args.join(" "));
+
+    let output = Command::new(program)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|_| Error::CommandFailed(command_line()))?;
+
+    if !output.status.success() {
+        return Err(Error::CommandFailed(command_line()));
+    }
+
+    let utf = String::from_utf8(output.stdout).map_err(|err| {
+        eprintln!("Failed to parse command output: {}", err);
+        Error::InvalidOutput
+    })?;
+
+    Ok(utf.trim().to_string())
+}
+
+/// Like [`run_cmd_to_output`], but captures stderr as well (appended
+/// after all of stdout). Used for `docker logs`, which writes a container's
+/// stderr to its own stderr rather than stdout.
+pub(crate) fn run_cmd_to_combined_output(program: &str, args: &[String]) -> Result<String, Error> {
+    let mut command = Command::new(program);
+
+    for arg in args {
+        command.arg(arg);
+    }
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let Ok(output) = command.output() else {
+        return Ok(String::new());
+    };
+
+    let mut combined = output.stdout;
+    combined.extend(output.stderr);
+
+    let utf = String::from_utf8(combined).map_err(|err| {
+        eprintln!("Failed to parse command output: {}", err);
+        Error::InvalidOutput
+    })?;
+
+    Ok(utf.trim().to_string())
+}
+
+fn parse_exposed_port(s: &str) -> Result<SocketAddr, Error> {
+    let parts: Vec<_> = s
+        .split_whitespace()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    parts
+        .iter()
+        .map(|p| {
+            p.parse::<SocketAddr>().map_err(|err| {
+                eprintln!("Failed to parse socket addr: {}", err);
+                Error::FailedToParsePorts
+            })
+        })
+        .next()
+        .ok_or(Error::FailedToParsePorts)?
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("0.0.0.0:55837" => 55837 ; "base case")]
+    #[test_case("   0.0.0.0:55837    " => 55837 ; "ignore whitespace")]
+    #[test_case("[::]:12345" => 12345 ; "works with ipv6")]
+    #[test_case("0.0.0.0:12345 \n [::]:12345" => 12345 ; "works with multiple ips")]
+    #[test_case("0.0.0.0:12345 \n [::]:54321" => 12345 ; "yields first of multiple ips")]
+    fn test_parse_exposed_port(s: &str) -> u16 {
+        parse_exposed_port(s).unwrap().port()
+    }
+
+    #[tokio::test]
+    async fn test_cp_and_output_roundtrip() {
+        let container = GenericImage::new("redis", "latest").spawn().await.unwrap();
+
+        container.cp("/tmp/hello.txt", b"hello world").unwrap();
+
+        let output = container.output(&["cat", "/tmp/hello.txt"]).unwrap();
+
+        assert_eq!(output, "hello world");
+    }
+
+    #[tokio::test]
+    async fn test_cp_to_missing_directory_fails() {
+        let container = GenericImage::new("redis", "latest").spawn().await.unwrap();
+
+        let result = container.cp("/no/such/directory/hello.txt", b"hello world");
+
+        assert!(matches!(result, Err(Error::CommandFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_output_of_missing_file_fails() {
+        let container = GenericImage::new("redis", "latest").spawn().await.unwrap();
+
+        let result = container.output(&["cat", "/no/such/file.txt"]);
+
+        assert!(matches!(result, Err(Error::CommandFailed(_))));
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{Container, Error, GenericImage};
+
+const MYSQL_PORT: u16 = 3306;
+
+/// A running `mysql` docker container, spawned with an empty root password
+/// and a random host port. The container is stopped and removed when
+/// dropped.
+pub struct MySQL {
+    container: Container,
+}
+
+impl MySQL {
+    /// Starts a mysql docker container that will accept root connections
+    /// with a random port assigned by docker. The container will be stopped
+    /// and removed when the guard is dropped.
+    pub async fn spawn() -> Result<Self, Error> {
+        let container = GenericImage::new("mysql", "latest")
+            .with_exposed_port(MYSQL_PORT)
+            .with_env_var("MYSQL_ALLOW_EMPTY_PASSWORD", "yes")
+            .spawn()
+            .await?;
+
+        Ok(MySQL { container })
+    }
+
+    pub fn socket_addr(&self) -> std::net::SocketAddr {
+        self.container
+            .socket_addr(MYSQL_PORT)
+            .expect("mysql port is always exposed")
+    }
+
+    pub fn address(&self) -> String {
+        self.socket_addr().to_string()
+    }
+}
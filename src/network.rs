@@ -0,0 +1,88 @@
+use crate::container::run_cmd;
+use crate::Error;
+
+/// A user-defined docker network, so that containers spawned onto it can
+/// reach each other by container name instead of only via host-mapped
+/// ports.
+///
+/// Runs `docker network create` on construction and `docker network rm`
+/// when dropped.
+pub struct Network {
+    name: String,
+}
+
+impl Network {
+    /// Creates a new docker network named `name`.
+    pub async fn create(name: impl Into<String>) -> Result<Self, Error> {
+        let name = name.into();
+
+        run_cmd(
+            "docker",
+            &[
+                "network".to_string(),
+                "create".to_string(),
+                name.clone(),
+            ],
+        )?;
+
+        Ok(Network { name })
+    }
+
+    /// The name passed to `docker network create`, also what containers on
+    /// this network are reachable by.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Drop for Network {
+    fn drop(&mut self) {
+        if let Err(err) = run_cmd(
+            "docker",
+            &["network".to_string(), "rm".to_string(), self.name.clone()],
+        ) {
+            eprintln!("Failed to remove docker network: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use crate::GenericImage;
+
+    use super::*;
+
+    static NETWORK_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    /// A name unique to this process, so repeated or parallel test runs
+    /// against the same docker host don't collide on a stale network name.
+    fn unique_network_name() -> String {
+        let counter = NETWORK_COUNTER.fetch_add(1, Ordering::Relaxed);
+        format!("docker-db-test-network-{}-{counter}", std::process::id())
+    }
+
+    #[tokio::test]
+    async fn test_containers_on_same_network_can_reach_each_other() {
+        let network = Network::create(unique_network_name()).await.unwrap();
+
+        let server = GenericImage::new("redis", "latest")
+            .with_network(&network)
+            .spawn()
+            .await
+            .unwrap();
+
+        let client = GenericImage::new("redis", "latest")
+            .with_network(&network)
+            .spawn()
+            .await
+            .unwrap();
+
+        let output = client
+            .output(&["redis-cli", "-h", server.name(), "ping"])
+            .unwrap();
+
+        assert_eq!(output, "PONG");
+    }
+}
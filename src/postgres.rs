@@ -0,0 +1,224 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::{Container, Error, GenericImage, WaitStrategy};
+
+const POSTGRES_PORT: u16 = 5432;
+const READY_LOG_MESSAGE: &str = "database system is ready to accept connections";
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_USER: &str = "postgres";
+const DEFAULT_DBNAME: &str = "postgres";
+
+/// A running `postgres` docker container. The container is stopped and
+/// removed when dropped.
+pub struct Postgres {
+    container: Container,
+    user: String,
+    password: Option<String>,
+    dbname: String,
+}
+
+impl Postgres {
+    /// Starts a postgres docker container with trust auth and a random
+    /// host port, equivalent to `Postgres::builder().spawn()`. The
+    /// container will be stopped and removed when the guard is dropped.
+    pub async fn spawn() -> Result<Self, Error> {
+        PostgresBuilder::default().spawn().await
+    }
+
+    /// Returns a builder for configuring the image tag, env vars and pull
+    /// policy before spawning.
+    pub fn builder() -> PostgresBuilder {
+        PostgresBuilder::default()
+    }
+
+    pub fn socket_addr(&self) -> SocketAddr {
+        self.container
+            .socket_addr(POSTGRES_PORT)
+            .expect("postgres port is always exposed")
+    }
+
+    pub fn address(&self) -> String {
+        self.socket_addr().to_string()
+    }
+
+    /// A ready-to-use libpq connection string for this container, e.g.
+    /// `postgres://postgres@127.0.0.1:55432/postgres`.
+    pub fn connection_string(&self) -> String {
+        let addr = self.socket_addr();
+        format!(
+            "postgres://{}@{}:{}/{}",
+            self.auth(),
+            addr.ip(),
+            addr.port(),
+            self.dbname
+        )
+    }
+
+    fn auth(&self) -> String {
+        match &self.password {
+            Some(password) => format!("{}:{password}", self.user),
+            None => self.user.clone(),
+        }
+    }
+}
+
+/// Builds a libpq connection string spanning several Postgres containers
+/// (e.g. a set of replicas spawned together), using the `host=a,b
+/// port=5432,5433` multi-host form. Every container's user/password/dbname
+/// must match - the crate doesn't attempt to reconcile containers with
+/// differing auth. Fails with [`Error::EmptyContainerList`] if `containers`
+/// is empty.
+pub fn multi_connection_string(containers: &[&Postgres]) -> Result<String, Error> {
+    let Some(first) = containers.first() else {
+        return Err(Error::EmptyContainerList);
+    };
+
+    let hosts: Vec<String> = containers
+        .iter()
+        .map(|c| c.socket_addr().ip().to_string())
+        .collect();
+    let ports: Vec<String> = containers
+        .iter()
+        .map(|c| c.socket_addr().port().to_string())
+        .collect();
+
+    let mut parts = vec![format!("user={}", first.user)];
+    if let Some(password) = &first.password {
+        parts.push(format!("password={password}"));
+    }
+    parts.push(format!("dbname={}", first.dbname));
+    parts.push(format!("host={}", hosts.join(",")));
+    parts.push(format!("port={}", ports.join(",")));
+
+    Ok(parts.join(" "))
+}
+
+/// Builder for a [`Postgres`] container, on top of [`GenericImage`].
+///
+/// Defaults to `postgres:latest` with trust auth (no password required).
+pub struct PostgresBuilder {
+    image: GenericImage,
+    user: String,
+    password: Option<String>,
+    dbname: String,
+}
+
+impl Default for PostgresBuilder {
+    fn default() -> Self {
+        PostgresBuilder {
+            image: GenericImage::new("postgres", "latest")
+                .with_exposed_port(POSTGRES_PORT)
+                .with_wait_strategy(WaitStrategy::log_message(READY_LOG_MESSAGE, READY_TIMEOUT)),
+            user: DEFAULT_USER.to_string(),
+            password: None,
+            dbname: DEFAULT_DBNAME.to_string(),
+        }
+    }
+}
+
+impl PostgresBuilder {
+    /// Overrides the `postgres` image tag, e.g. `.tag("16-alpine")`.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.image = self.image.with_tag(tag);
+        self
+    }
+
+    /// Sets an environment variable inside the container, e.g.
+    /// `.env("POSTGRES_PASSWORD", "hunter2")`. `POSTGRES_USER`,
+    /// `POSTGRES_PASSWORD` and `POSTGRES_DB` are also tracked so that
+    /// [`Postgres::connection_string`] reflects them.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let key = key.into();
+        let value = value.into();
+
+        match key.as_str() {
+            "POSTGRES_USER" => self.user = value.clone(),
+            "POSTGRES_PASSWORD" => self.password = Some(value.clone()),
+            "POSTGRES_DB" => self.dbname = value.clone(),
+            _ => {}
+        }
+
+        self.image = self.image.with_env_var(key, value);
+        self
+    }
+
+    /// When `true`, pulls the image before starting it rather than relying
+    /// on whatever is already cached locally.
+    pub fn pull(mut self, pull: bool) -> Self {
+        self.image = self.image.with_pull(pull);
+        self
+    }
+
+    pub async fn spawn(self) -> Result<Postgres, Error> {
+        // Only fall back to trust auth if the caller hasn't configured a
+        // password - setting both would make the official image ignore
+        // POSTGRES_PASSWORD and accept connections from anyone regardless.
+        let image = if self.password.is_none() {
+            self.image
+                .with_env_var("POSTGRES_HOST_AUTH_METHOD", "trust")
+        } else {
+            self.image
+        };
+
+        let container = image.spawn().await?;
+
+        Ok(Postgres {
+            container,
+            user: self.user,
+            password: self.password,
+            dbname: self.dbname,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_spawn() {
+        let _ = Postgres::spawn().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_connection_string() {
+        let postgres = Postgres::spawn().await.unwrap();
+        let addr = postgres.socket_addr();
+
+        assert_eq!(
+            postgres.connection_string(),
+            format!("postgres://postgres@{}:{}/postgres", addr.ip(), addr.port())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_connection_string() {
+        let a = Postgres::spawn().await.unwrap();
+        let b = Postgres::spawn().await.unwrap();
+
+        let conn_string = multi_connection_string(&[&a, &b]).unwrap();
+
+        let addr_a = a.socket_addr();
+        let addr_b = b.socket_addr();
+
+        assert_eq!(
+            conn_string,
+            format!(
+                "user=postgres dbname=postgres host={},{} port={},{}",
+                addr_a.ip(),
+                addr_b.ip(),
+                addr_a.port(),
+                addr_b.port()
+            )
+        );
+    }
+
+    #[test]
+    fn test_multi_connection_string_empty() {
+        assert!(matches!(
+            multi_connection_string(&[]),
+            Err(Error::EmptyContainerList)
+        ));
+    }
+}
@@ -0,0 +1,33 @@
+use crate::{Container, Error, GenericImage};
+
+const REDIS_PORT: u16 = 6379;
+
+/// A running `redis` docker container with a random host port. The
+/// container is stopped and removed when dropped.
+pub struct Redis {
+    container: Container,
+}
+
+impl Redis {
+    /// Starts a redis docker container with a random port assigned by
+    /// docker. The container will be stopped and removed when the guard is
+    /// dropped.
+    pub async fn spawn() -> Result<Self, Error> {
+        let container = GenericImage::new("redis", "latest")
+            .with_exposed_port(REDIS_PORT)
+            .spawn()
+            .await?;
+
+        Ok(Redis { container })
+    }
+
+    pub fn socket_addr(&self) -> std::net::SocketAddr {
+        self.container
+            .socket_addr(REDIS_PORT)
+            .expect("redis port is always exposed")
+    }
+
+    pub fn address(&self) -> String {
+        self.socket_addr().to_string()
+    }
+}
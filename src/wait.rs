@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::net::{SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+
+use crate::container::run_cmd_to_combined_output;
+use crate::Error;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A strategy for deciding when a freshly spawned container is ready to
+/// accept requests.
+///
+/// Replaces a blind fixed-length sleep: the container is polled on a short
+/// interval until it looks ready, or [`Error::Timeout`] is returned once the
+/// strategy's timeout elapses.
+pub enum WaitStrategy {
+    /// Don't wait for anything - the container is considered ready as soon
+    /// as docker reports it running.
+    None,
+
+    /// Retry connecting to one of the container's mapped ports until the
+    /// connection succeeds.
+    Tcp { port: u16, timeout: Duration },
+
+    /// Poll `docker logs <id>` until a substring appears in the output.
+    LogMessage {
+        message: String,
+        timeout: Duration,
+    },
+}
+
+impl WaitStrategy {
+    /// Retry connecting to `port` until it succeeds or `timeout` elapses.
+    pub fn tcp(port: u16, timeout: Duration) -> Self {
+        WaitStrategy::Tcp { port, timeout }
+    }
+
+    /// Poll the container's logs until `message` appears or `timeout`
+    /// elapses.
+    pub fn log_message(message: impl Into<String>, timeout: Duration) -> Self {
+        WaitStrategy::LogMessage {
+            message: message.into(),
+            timeout,
+        }
+    }
+
+    pub(crate) fn wait(
+        &self,
+        container_id: &str,
+        ports: &HashMap<u16, SocketAddr>,
+    ) -> Result<(), Error> {
+        match self {
+            WaitStrategy::None => Ok(()),
+            WaitStrategy::Tcp { port, timeout } => {
+                let addr = *ports.get(port).ok_or(Error::PortNotExposed(*port))?;
+                wait_for_tcp(addr, *timeout)
+            }
+            WaitStrategy::LogMessage { message, timeout } => {
+                wait_for_log_message(container_id, message, *timeout)
+            }
+        }
+    }
+}
+
+fn wait_for_tcp(addr: SocketAddr, timeout: Duration) -> Result<(), Error> {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        if TcpStream::connect(addr).is_ok() {
+            return Ok(());
+        }
+
+        if Instant::now() >= deadline {
+            return Err(Error::Timeout);
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn wait_for_log_message(container_id: &str, message: &str, timeout: Duration) -> Result<(), Error> {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let logs =
+            run_cmd_to_combined_output("docker", &["logs".to_string(), container_id.to_string()])?;
+
+        if logs.contains(message) {
+            return Ok(());
+        }
+
+        if Instant::now() >= deadline {
+            return Err(Error::Timeout);
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wait_for_tcp_times_out_on_closed_port() {
+        let addr: SocketAddr = "127.0.0.1:1".parse().unwrap();
+
+        let result = wait_for_tcp(addr, Duration::from_millis(50));
+
+        assert!(matches!(result, Err(Error::Timeout)));
+    }
+
+    #[test]
+    fn test_wait_for_unexposed_port() {
+        let ports = HashMap::new();
+
+        let result = WaitStrategy::tcp(1234, Duration::from_millis(10)).wait("container_id", &ports);
+
+        assert!(matches!(result, Err(Error::PortNotExposed(1234))));
+    }
+}